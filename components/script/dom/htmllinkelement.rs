@@ -21,15 +21,17 @@ use dom::node::{Node, UnbindContext, document_from_node, window_from_node};
 use dom::stylesheet::StyleSheet as DOMStyleSheet;
 use dom::virtualmethods::VirtualMethods;
 use dom_struct::dom_struct;
-use embedder_traits::EmbedderMsg;
+use embedder_traits::{EmbedderMsg, FaviconDescriptor, IconSize};
 use html5ever::{LocalName, Prefix};
 use net_traits::ReferrerPolicy;
+use net_traits::request::{CorsSettings, Destination};
 use servo_arc::Arc;
+use servo_url::ServoUrl;
 use std::borrow::ToOwned;
 use std::cell::Cell;
 use std::default::Default;
 use style::attr::AttrValue;
-use style::media_queries::parse_media_query_list;
+use style::media_queries::{MediaList, parse_media_query_list};
 use style::parser::ParserContext as CssParserContext;
 use style::str::HTML_SPACE_CHARACTERS;
 use style::stylesheets::{CssRuleType, Stylesheet};
@@ -45,6 +47,18 @@ impl RequestGenerationId {
     }
 }
 
+/// An external stylesheet load deferred because the element's `media` query does
+/// not match the current viewport. Kept until the viewport changes and the query
+/// starts matching, at which point the fetch is finally issued.
+#[derive(JSTraceable, MallocSizeOf)]
+#[must_root]
+struct PendingStylesheetLoad {
+    #[ignore_malloc_size_of = "Defined in rust-url"]
+    url: ServoUrl,
+    cors_setting: Option<CorsSettings>,
+    integrity_metadata: String,
+}
+
 #[dom_struct]
 pub struct HTMLLinkElement {
     htmlelement: HTMLElement,
@@ -62,6 +76,14 @@ pub struct HTMLLinkElement {
     any_failed_load: Cell<bool>,
     /// A monotonically increasing counter that keeps track of which stylesheet to apply.
     request_generation_id: Cell<RequestGenerationId>,
+    /// A fetch deferred because the `media` attribute does not match the current
+    /// viewport; re-evaluated when the device changes. See #8085 in
+    /// `handle_stylesheet_url`.
+    pending_media_load: DomRefCell<Option<PendingStylesheetLoad>>,
+    /// The parsed `media` attribute, cached so enablement checks don't re-parse the
+    /// attribute string on every query. Refreshed when `media` is mutated.
+    #[ignore_malloc_size_of = "Defined in style"]
+    media: DomRefCell<Option<MediaList>>,
 }
 
 impl HTMLLinkElement {
@@ -76,6 +98,8 @@ impl HTMLLinkElement {
             pending_loads: Cell::new(0),
             any_failed_load: Cell::new(false),
             request_generation_id: Cell::new(RequestGenerationId(0)),
+            pending_media_load: DomRefCell::new(None),
+            media: DomRefCell::new(None),
         }
     }
 
@@ -101,14 +125,99 @@ impl HTMLLinkElement {
             doc.remove_stylesheet(self.upcast(), s)
         }
         *self.stylesheet.borrow_mut() = Some(s.clone());
-        self.cssom_stylesheet.set(None);
-        doc.add_stylesheet(self.upcast(), s);
+        // The old CSSOM wrapper, if any, no longer describes a live sheet: detach it
+        // so a script still holding `link.sheet` observes the spec-correct state.
+        self.detach_cssom_stylesheet();
+        // A disabled sheet, or one belonging to a non-selected stylesheet set, keeps
+        // its parsed `Arc<Stylesheet>` but is not applied to the document.
+        if self.is_sheet_enabled(&doc) {
+            doc.add_stylesheet(self.upcast(), s);
+        }
+    }
+
+    /// The name of the stylesheet set this link contributes to, derived from its
+    /// `title` attribute. An empty title is treated as no title.
+    fn style_sheet_set_name(&self) -> Option<String> {
+        get_attr(self.upcast(), &local_name!("title"))
+            .and_then(|t| if t.is_empty() { None } else { Some(t) })
+    }
+
+    /// If this is a titled, non-alternate sheet and the document has no preferred
+    /// set yet, its title becomes the preferred set. Called at bind time, so the
+    /// first such link to bind wins, not the first whose fetch completes.
+    /// <https://drafts.csswg.org/cssom/#the-stylesheet-processing-model>
+    fn establish_preferred_style_sheet_set(&self, doc: &Document) {
+        if self.is_alternate() {
+            return;
+        }
+        if let Some(title) = self.style_sheet_set_name() {
+            if doc.preferred_style_sheet_set().is_none() {
+                doc.set_preferred_style_sheet_set(&title);
+            }
+        }
+    }
+
+    /// Whether this link's sheet should currently be applied to the document,
+    /// honoring the `disabled` flag, the `media` query, and the preferred/alternate
+    /// set selection. This is a pure predicate: it reads state but mutates nothing.
+    /// <https://drafts.csswg.org/cssom/#the-stylesheet-processing-model>
+    fn is_sheet_enabled(&self, doc: &Document) -> bool {
+        if self.is_disabled() {
+            return false;
+        }
+        // A sheet whose media query doesn't match the viewport drops out of the
+        // active set without being refetched.
+        if let Some(ref media) = *self.media.borrow() {
+            if !media_matches(doc, media) {
+                return false;
+            }
+        }
+        match self.style_sheet_set_name() {
+            // Untitled sheets are persistent; an alternate sheet with no title never
+            // applies.
+            None => !self.is_alternate(),
+            Some(title) => match doc.selected_style_sheet_set() {
+                Some(ref selected) => *selected == title,
+                None => Some(&title) == doc.preferred_style_sheet_set().as_ref(),
+            },
+        }
+    }
+
+    /// Re-evaluate whether this link's sheet should be enabled for the currently
+    /// selected stylesheet set, applying or removing it from the document without a
+    /// new network request. Invoked by the `Document` when the selected set changes.
+    pub fn reevaluate_style_sheet_set(&self) {
+        let doc = document_from_node(self);
+        if let Some(ref s) = *self.stylesheet.borrow() {
+            if self.is_sheet_enabled(&doc) {
+                doc.add_stylesheet(self.upcast(), s);
+            } else {
+                doc.remove_stylesheet(self.upcast(), s);
+            }
+        }
     }
 
     pub fn get_stylesheet(&self) -> Option<Arc<Stylesheet>> {
         self.stylesheet.borrow().clone()
     }
 
+    /// Detach the CSSOM wrapper from this element, unsetting its owner node and
+    /// parent pointers per the CSSOM requirements for a removed sheet.
+    /// <https://drafts.csswg.org/cssom/#remove-a-css-style-sheet>
+    fn detach_cssom_stylesheet(&self) {
+        if let Some(stylesheet) = self.cssom_stylesheet.get() {
+            stylesheet.detach();
+        }
+        self.cssom_stylesheet.set(None);
+    }
+
+    /// Whether this element's sheet is disabled, mirroring the reflected `disabled`
+    /// content attribute. A disabled sheet keeps its parsed `Arc<Stylesheet>` but is
+    /// never applied to the document.
+    pub fn is_disabled(&self) -> bool {
+        self.upcast::<Element>().has_attribute(&local_name!("disabled"))
+    }
+
     pub fn get_cssom_stylesheet(&self) -> Option<DomRoot<CSSStyleSheet>> {
         self.get_stylesheet().map(|sheet| {
             self.cssom_stylesheet.or_init(|| {
@@ -142,6 +251,48 @@ fn get_attr(element: &Element, local_name: &LocalName) -> Option<String> {
     })
 }
 
+/// Every `rel="stylesheet"` link element in the document, in tree order.
+fn stylesheet_links(document: &Document) -> Vec<DomRoot<HTMLLinkElement>> {
+    document.upcast::<Node>().traverse_preorder()
+        .filter_map(|node| node.downcast::<HTMLLinkElement>().map(DomRoot::from_ref))
+        .filter(|link| string_is_stylesheet(&get_attr(link.upcast(), &local_name!("rel"))))
+        .collect()
+}
+
+/// Every `rel="icon"`/`rel="apple-touch-icon"` link element in the document, in
+/// tree order.
+fn favicon_links(document: &Document) -> Vec<DomRoot<HTMLLinkElement>> {
+    document.upcast::<Node>().traverse_preorder()
+        .filter_map(|node| node.downcast::<HTMLLinkElement>().map(DomRoot::from_ref))
+        .filter(|link| is_favicon(&get_attr(link.upcast(), &local_name!("rel"))))
+        .collect()
+}
+
+/// Recompute the preferred stylesheet set from tree order: the first titled,
+/// non-alternate stylesheet link wins.
+/// <https://drafts.csswg.org/cssom/#the-stylesheet-processing-model>
+fn reestablish_preferred_style_sheet_set(document: &Document) {
+    for link in stylesheet_links(document) {
+        if !link.is_alternate() {
+            if let Some(title) = link.style_sheet_set_name() {
+                document.set_preferred_style_sheet_set(&title);
+                return;
+            }
+        }
+    }
+}
+
+/// Whether `media` matches the document's current viewport. An empty media list
+/// (no `media` attribute) always matches.
+fn media_matches(document: &Document, media: &MediaList) -> bool {
+    match document.device() {
+        Some(ref device) => media.evaluate(device, document.quirks_mode()),
+        // Before layout has established a viewport there is nothing to evaluate
+        // against, so treat the sheet as applicable and let it be fetched.
+        None => true,
+    }
+}
+
 fn string_is_stylesheet(value: &Option<String>) -> bool {
     match *value {
         Some(ref value) => {
@@ -165,6 +316,48 @@ fn is_favicon(value: &Option<String>) -> bool {
     }
 }
 
+/// The speculative-loading link relationships handled by the resource-hints
+/// subsystem.
+/// <https://html.spec.whatwg.org/multipage/#linkTypes>
+#[derive(Clone, Copy, PartialEq)]
+enum ResourceHint {
+    Preload,
+    Prefetch,
+    Preconnect,
+    DnsPrefetch,
+}
+
+fn resource_hint(value: &Option<String>) -> Option<ResourceHint> {
+    match *value {
+        Some(ref value) => value.split(HTML_SPACE_CHARACTERS).filter_map(|s| {
+            if s.eq_ignore_ascii_case("preload") {
+                Some(ResourceHint::Preload)
+            } else if s.eq_ignore_ascii_case("prefetch") {
+                Some(ResourceHint::Prefetch)
+            } else if s.eq_ignore_ascii_case("preconnect") {
+                Some(ResourceHint::Preconnect)
+            } else if s.eq_ignore_ascii_case("dns-prefetch") {
+                Some(ResourceHint::DnsPrefetch)
+            } else {
+                None
+            }
+        }).next(),
+        None => None,
+    }
+}
+
+/// Map the `as` attribute to a request destination for `rel="preload"`.
+/// <https://html.spec.whatwg.org/multipage/#attr-link-as>
+fn destination_for_as(value: &str) -> Option<Destination> {
+    match &*value.to_ascii_lowercase() {
+        "style" => Some(Destination::Style),
+        "script" => Some(Destination::Script),
+        "image" => Some(Destination::Image),
+        "font" => Some(Destination::Font),
+        _ => None,
+    }
+}
+
 impl VirtualMethods for HTMLLinkElement {
     fn super_type(&self) -> Option<&VirtualMethods> {
         Some(self.upcast::<HTMLElement>() as &VirtualMethods)
@@ -172,11 +365,50 @@ impl VirtualMethods for HTMLLinkElement {
 
     fn attribute_mutated(&self, attr: &Attr, mutation: AttributeMutation) {
         self.super_type().unwrap().attribute_mutated(attr, mutation);
-        if !self.upcast::<Node>().is_in_doc() || mutation.is_removal() {
+        if !self.upcast::<Node>().is_in_doc() {
             return;
         }
 
         let rel = get_attr(self.upcast(), &local_name!("rel"));
+
+        // `disabled`, `title`, and `media` change which already-loaded sheet
+        // applies, so they must be honored on removal as well as addition.
+        match attr.local_name() {
+            &local_name!("disabled") => {
+                // Toggling the reflected attribute applies or removes the
+                // already-parsed sheet without a refetch.
+                if string_is_stylesheet(&rel) {
+                    self.reevaluate_style_sheet_set();
+                }
+                return;
+            },
+            &local_name!("title") => {
+                if string_is_stylesheet(&rel) {
+                    // The defining link's title may have changed, so recompute the
+                    // preferred set from tree order and re-evaluate every sibling
+                    // whose set membership could have shifted.
+                    let document = document_from_node(self);
+                    reestablish_preferred_style_sheet_set(&document);
+                    for link in stylesheet_links(&document) {
+                        link.reevaluate_style_sheet_set();
+                    }
+                }
+                return;
+            },
+            &local_name!("media") => {
+                if string_is_stylesheet(&rel) {
+                    *self.media.borrow_mut() = Some(self.parse_media(&document_from_node(self)));
+                    self.reevaluate_media();
+                }
+                return;
+            },
+            _ => {},
+        }
+
+        if mutation.is_removal() {
+            return;
+        }
+
         match attr.local_name() {
             &local_name!("href") => {
                 if string_is_stylesheet(&rel) {
@@ -184,6 +416,8 @@ impl VirtualMethods for HTMLLinkElement {
                 } else if is_favicon(&rel) {
                     let sizes = get_attr(self.upcast(), &local_name!("sizes"));
                     self.handle_favicon_url(rel.as_ref().unwrap(), &attr.value(), &sizes);
+                } else if let Some(hint) = resource_hint(&rel) {
+                    self.handle_resource_hint(hint, &attr.value());
                 }
             },
             &local_name!("sizes") => {
@@ -193,6 +427,13 @@ impl VirtualMethods for HTMLLinkElement {
                     }
                 }
             },
+            &local_name!("as") => {
+                if let Some(hint) = resource_hint(&rel) {
+                    if let Some(ref href) = get_attr(self.upcast(), &local_name!("href")) {
+                        self.handle_resource_hint(hint, href);
+                    }
+                }
+            },
             _ => {},
         }
     }
@@ -216,6 +457,13 @@ impl VirtualMethods for HTMLLinkElement {
             let href = get_attr(element, &local_name!("href"));
             let sizes = get_attr(self.upcast(), &local_name!("sizes"));
 
+            // Establish the preferred set from tree order: the first titled,
+            // non-alternate stylesheet link to bind wins, independent of when its
+            // fetch completes.
+            if string_is_stylesheet(&rel) {
+                self.establish_preferred_style_sheet_set(&document_from_node(self));
+            }
+
             match href {
                 Some(ref href) if string_is_stylesheet(&rel) => {
                     self.handle_stylesheet_url(href);
@@ -223,6 +471,11 @@ impl VirtualMethods for HTMLLinkElement {
                 Some(ref href) if is_favicon(&rel) => {
                     self.handle_favicon_url(rel.as_ref().unwrap(), href, &sizes);
                 }
+                Some(ref href) => {
+                    if let Some(hint) = resource_hint(&rel) {
+                        self.handle_resource_hint(hint, href);
+                    }
+                }
                 _ => {}
             }
         }
@@ -233,9 +486,16 @@ impl VirtualMethods for HTMLLinkElement {
             s.unbind_from_tree(context);
         }
 
+        let document = document_from_node(self);
+        document.unregister_media_dependent_link(self);
+        *self.pending_media_load.borrow_mut() = None;
+
         if let Some(s) = self.stylesheet.borrow_mut().take() {
-            document_from_node(self).remove_stylesheet(self.upcast(), &s);
+            document.remove_stylesheet(self.upcast(), &s);
         }
+        // Removing the link from the tree removes its sheet from the StyleSheetList,
+        // which must unset the CSSOM wrapper's owner node and parent pointers.
+        self.detach_cssom_stylesheet();
     }
 }
 
@@ -267,6 +527,53 @@ impl HTMLLinkElement {
         // Step 3
         let cors_setting = cors_setting_for_element(element);
 
+        let media = self.parse_media(&document);
+        *self.media.borrow_mut() = Some(media.clone());
+
+        // Any link carrying a media query must be re-evaluated on viewport changes,
+        // in both directions: a query that stops matching removes the applied sheet,
+        // and one that starts matching issues its deferred fetch. Register up front
+        // rather than only when the query fails initially.
+        if element.has_attribute(&local_name!("media")) {
+            document.register_media_dependent_link(self);
+        }
+
+        let im_attribute = element.get_attribute(&ns!(), &local_name!("integrity"));
+        let integrity_val = im_attribute.r().map(|a| a.value());
+        let integrity_metadata = match integrity_val {
+            Some(ref value) => &***value,
+            None => "",
+        };
+
+        // #8085 - Don't load external stylesheets whose media query doesn't match
+        // the current viewport; keep the request around so that a later viewport
+        // change can issue it without re-parsing the attributes.
+        if !media_matches(&document, &media) {
+            *self.pending_media_load.borrow_mut() = Some(PendingStylesheetLoad {
+                url: link_url,
+                cors_setting,
+                integrity_metadata: integrity_metadata.to_owned(),
+            });
+            return;
+        }
+
+        self.load_stylesheet(link_url, cors_setting, integrity_metadata.to_owned(), media);
+    }
+
+    /// Issue the actual stylesheet fetch through the loader infrastructure.
+    fn load_stylesheet(&self, url: ServoUrl, cors_setting: Option<CorsSettings>,
+                       integrity_metadata: String, media: MediaList) {
+        self.request_generation_id.set(self.request_generation_id.get().increment());
+
+        let loader = StylesheetLoader::for_element(self.upcast());
+        loader.load(StylesheetContextSource::LinkElement {
+            media: Some(media),
+        }, url, cors_setting, integrity_metadata);
+    }
+
+    /// Parse the element's `media` attribute into a `MediaList`.
+    fn parse_media(&self, document: &Document) -> MediaList {
+        let element = self.upcast::<Element>();
         let mq_attribute = element.get_attribute(&ns!(), &local_name!("media"));
         let value = mq_attribute.r().map(|a| a.value());
         let mq_str = match value {
@@ -281,40 +588,179 @@ impl HTMLLinkElement {
                                                       ParsingMode::DEFAULT,
                                                       document.quirks_mode());
         let window = document.window();
-        let media = parse_media_query_list(&context, &mut css_parser,
-                                           window.css_error_reporter());
+        parse_media_query_list(&context, &mut css_parser, window.css_error_reporter())
+    }
 
-        let im_attribute = element.get_attribute(&ns!(), &local_name!("integrity"));
-        let integrity_val = im_attribute.r().map(|a| a.value());
-        let integrity_metadata = match integrity_val {
-            Some(ref value) => &***value,
-            None => "",
+    /// Re-evaluate the element's media query against the current viewport. A
+    /// deferred fetch whose query now matches is finally issued; an already-applied
+    /// sheet whose query stopped matching is removed from the active set (and
+    /// re-added when it matches again) without a new network request.
+    pub fn reevaluate_media(&self) {
+        let document = document_from_node(self);
+        let pending = self.pending_media_load.borrow_mut().take();
+        if let Some(load) = pending {
+            let media = self.media.borrow().clone().unwrap_or_else(|| self.parse_media(&document));
+            if media_matches(&document, &media) {
+                self.load_stylesheet(load.url, load.cors_setting, load.integrity_metadata, media);
+            } else {
+                // Still not applicable; keep waiting for a future viewport change.
+                *self.pending_media_load.borrow_mut() = Some(load);
+            }
+            return;
+        }
+
+        self.reevaluate_style_sheet_set();
+    }
+
+    /// Initiate speculative loading for a resource-hint link type.
+    /// <https://html.spec.whatwg.org/multipage/#linkTypes>
+    fn handle_resource_hint(&self, hint: ResourceHint, href: &str) {
+        let document = document_from_node(self);
+        if document.browsing_context().is_none() {
+            return;
+        }
+
+        if href.is_empty() {
+            return;
+        }
+
+        let url = match document.base_url().join(href) {
+            Ok(url) => url,
+            Err(e) => {
+                debug!("Parsing url {} failed: {}", href, e);
+                return;
+            }
         };
 
-        self.request_generation_id.set(self.request_generation_id.get().increment());
+        match hint {
+            ResourceHint::Preload | ResourceHint::Prefetch => {
+                let destination = get_attr(self.upcast(), &local_name!("as"))
+                    .as_ref()
+                    .and_then(|value| destination_for_as(value));
+                // `preload` without a recognized `as` destination is a no-op; a later
+                // real request has nothing to reuse.
+                if hint == ResourceHint::Preload && destination.is_none() {
+                    return;
+                }
+                // The loader-backed preload drives its own pending-load accounting
+                // and fires `load`/`error` through the `StylesheetOwner` hooks, just
+                // like the stylesheet path — don't double-count here.
+                document.preload(self.upcast(), url, destination);
+            },
+            ResourceHint::Preconnect | ResourceHint::DnsPrefetch => {
+                // Warm up the connection to the resolved origin without fetching.
+                let window = document.window();
+                window.send_to_embedder(EmbedderMsg::Preconnect(url));
+            },
+        }
+    }
 
-        // TODO: #8085 - Don't load external stylesheets if the node's mq
-        // doesn't match.
-        let loader = StylesheetLoader::for_element(self.upcast());
-        loader.load(StylesheetContextSource::LinkElement {
-            media: Some(media),
-        }, link_url, cors_setting, integrity_metadata.to_owned());
+    /// The icon descriptor this link contributes, or `None` if it is not a favicon
+    /// link or its `href` can't be resolved.
+    fn favicon_descriptor(&self) -> Option<FaviconDescriptor> {
+        let element = self.upcast::<Element>();
+        let rel = get_attr(element, &local_name!("rel"));
+        if !is_favicon(&rel) {
+            return None;
+        }
+        let href = get_attr(element, &local_name!("href"))?;
+        let url = document_from_node(self).base_url().join(&href).ok()?;
+        let is_apple_touch_icon = rel.as_ref().unwrap().split(HTML_SPACE_CHARACTERS)
+            .any(|s| s.eq_ignore_ascii_case("apple-touch-icon"));
+        Some(FaviconDescriptor {
+            url,
+            sizes: parse_sizes(&get_attr(element, &local_name!("sizes"))),
+            mime_type: get_attr(element, &local_name!("type")),
+            is_apple_touch_icon,
+        })
     }
 
-    fn handle_favicon_url(&self, _rel: &str, href: &str, _sizes: &Option<String>) {
+    fn handle_favicon_url(&self, _rel: &str, _href: &str, _sizes: &Option<String>) {
         let document = document_from_node(self);
-        match document.base_url().join(href) {
-            Ok(url) => {
-                let window = document.window();
-                if window.is_top_level() {
-                    let msg = EmbedderMsg::NewFavicon(url.clone());
-                    window.send_to_embedder(msg);
-                }
+        let window = document.window();
+        if !window.is_top_level() {
+            return;
+        }
+
+        // Pick the single best-fitting icon among all declared favicon links rather
+        // than emitting one `NewFavicon` per link and letting the last one bound
+        // win. Re-runs when a favicon link is bound or its `href` or `sizes` is
+        // set; changing `type`, removing an attribute or unbinding a link does not.
+        let candidates: Vec<FaviconDescriptor> = favicon_links(&document).iter()
+            .filter_map(|link| link.favicon_descriptor())
+            .collect();
+        if let Some(best) = select_best_favicon(&candidates, PREFERRED_FAVICON_SIZE) {
+            window.send_to_embedder(EmbedderMsg::NewFavicon(candidates[best].clone()));
+        }
+    }
+}
 
+/// The display size, in CSS pixels, the favicon selection aims for when the
+/// embedder hasn't requested a specific resolution.
+const PREFERRED_FAVICON_SIZE: u32 = 32;
+
+/// Parse a favicon `sizes` token list into its entries. Recognizes the `any`
+/// keyword and whitespace-separated `WxH` pixel descriptors; unparseable tokens
+/// are ignored.
+/// <https://html.spec.whatwg.org/multipage/#attr-link-sizes>
+fn parse_sizes(value: &Option<String>) -> Vec<IconSize> {
+    let value = match *value {
+        Some(ref value) => value,
+        None => return Vec::new(),
+    };
+    value.split(HTML_SPACE_CHARACTERS).filter_map(|token| {
+        if token.is_empty() {
+            None
+        } else if token.eq_ignore_ascii_case("any") {
+            Some(IconSize::Any)
+        } else {
+            let mut parts = token.split(|c| c == 'x' || c == 'X');
+            let width = parts.next().and_then(|w| w.parse::<u32>().ok());
+            let height = parts.next().and_then(|h| h.parse::<u32>().ok());
+            match (width, height, parts.next()) {
+                (Some(width), Some(height), None) => Some(IconSize::Fixed(width, height)),
+                _ => None,
             }
-            Err(e) => debug!("Parsing url {} failed: {}", href, e)
+        }
+    }).collect()
+}
+
+/// Rank how well a favicon fits a desired square display size (lower is better):
+/// an exact match wins, then the smallest icon larger than the target, then a
+/// scalable icon, then the largest icon smaller than the target.
+fn descriptor_rank(descriptor: &FaviconDescriptor, desired: u32) -> (u8, u32) {
+    let scalable = descriptor.sizes.iter().any(|s| *s == IconSize::Any) ||
+        descriptor.mime_type.as_ref().map_or(false, |t| t.eq_ignore_ascii_case("image/svg+xml"));
+
+    let mut best = if scalable { Some((2u8, 0u32)) } else { None };
+    for size in &descriptor.sizes {
+        if let IconSize::Fixed(width, height) = *size {
+            let edge = width.max(height);
+            let rank = if edge == desired {
+                (0, 0)
+            } else if edge > desired {
+                (1, edge - desired)
+            } else {
+                (3, desired - edge)
+            };
+            best = Some(match best {
+                Some(current) if current <= rank => current,
+                _ => rank,
+            });
         }
     }
+
+    // An icon without any parsed size is only a last resort.
+    best.unwrap_or((4, 0))
+}
+
+/// Choose the best-fitting icon among `descriptors` for a desired square display
+/// size in pixels, returning its index.
+fn select_best_favicon(descriptors: &[FaviconDescriptor], desired: u32) -> Option<usize> {
+    descriptors.iter()
+        .enumerate()
+        .min_by_key(|&(_, descriptor)| descriptor_rank(descriptor, desired))
+        .map(|(index, _)| index)
 }
 
 impl StylesheetOwner for HTMLLinkElement {
@@ -396,6 +842,12 @@ impl HTMLLinkElementMethods for HTMLLinkElement {
     // https://html.spec.whatwg.org/multipage/#dom-link-type
     make_setter!(SetType, "type");
 
+    // https://html.spec.whatwg.org/multipage/#dom-link-as
+    make_getter!(As, "as");
+
+    // https://html.spec.whatwg.org/multipage/#dom-link-as
+    make_setter!(SetAs, "as");
+
     // https://html.spec.whatwg.org/multipage/#dom-link-rellist
     fn RelList(&self) -> DomRoot<DOMTokenList> {
         self.rel_list.or_init(|| DOMTokenList::new(self.upcast(), &local_name!("rel")))
@@ -433,4 +885,10 @@ impl HTMLLinkElementMethods for HTMLLinkElement {
     fn GetSheet(&self) -> Option<DomRoot<DOMStyleSheet>> {
         self.get_cssom_stylesheet().map(DomRoot::upcast)
     }
+
+    // https://drafts.csswg.org/cssom/#dom-linkstyle-disabled
+    make_bool_getter!(Disabled, "disabled");
+
+    // https://drafts.csswg.org/cssom/#dom-linkstyle-disabled
+    make_bool_setter!(SetDisabled, "disabled");
 }